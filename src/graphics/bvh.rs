@@ -0,0 +1,295 @@
+extern crate cgmath;
+
+use cgmath::{InnerSpace, Point3, Vector3};
+use graphics::mesh::Mesh;
+use std::f32;
+use std::mem::swap;
+
+/** Number of triangles at or below which a BVH node becomes a leaf. */
+const LEAF_THRESHOLD: usize = 4;
+
+/** An axis-aligned bounding box. */
+#[derive(Clone, Copy, Debug)]
+pub struct Aabb {
+    pub min: Point3<f32>,
+    pub max: Point3<f32>,
+}
+
+impl Aabb {
+    /** An AABB which contains nothing; unioning/extending it yields the other operand. */
+    pub fn empty() -> Aabb {
+        Aabb {
+            min: Point3::new(f32::INFINITY, f32::INFINITY, f32::INFINITY),
+            max: Point3::new(f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY),
+        }
+    }
+
+    pub fn extend(&mut self, p: Point3<f32>) {
+        self.min.x = self.min.x.min(p.x);
+        self.min.y = self.min.y.min(p.y);
+        self.min.z = self.min.z.min(p.z);
+        self.max.x = self.max.x.max(p.x);
+        self.max.y = self.max.y.max(p.y);
+        self.max.z = self.max.z.max(p.z);
+    }
+
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        let mut result = *self;
+        result.extend(other.min);
+        result.extend(other.max);
+        result
+    }
+
+    /** Slab test against a ray, returning the entry `t` if it hits within `[0, t_max]`. */
+    pub fn intersect(&self, origin: Point3<f32>, inv_dir: Vector3<f32>, t_max: f32) -> Option<f32> {
+        let mut t_min = 0.0f32;
+        let mut t_max = t_max;
+
+        for axis in 0..3 {
+            let (o, d, lo, hi) = match axis {
+                0 => (origin.x, inv_dir.x, self.min.x, self.max.x),
+                1 => (origin.y, inv_dir.y, self.min.y, self.max.y),
+                _ => (origin.z, inv_dir.z, self.min.z, self.max.z),
+            };
+
+            let mut t0 = (lo - o) * d;
+            let mut t1 = (hi - o) * d;
+            if t0 > t1 {
+                swap(&mut t0, &mut t1);
+            }
+
+            t_min = t_min.max(t0);
+            t_max = t_max.min(t1);
+            if t_min > t_max {
+                return None;
+            }
+        }
+        Some(t_min)
+    }
+}
+
+/** A node in the BVH, stored in a flat arena and addressed by index. */
+enum Node {
+    Inner { bounds: Aabb, left: usize, right: usize },
+    Leaf { bounds: Aabb, triangles: Vec<usize> },
+}
+
+/** The result of a successful ray-mesh intersection. */
+#[derive(Debug)]
+pub struct Hit {
+    pub t: f32,
+    pub triangle_index: usize,
+    pub bary: (f32, f32),
+}
+
+/** A bounding-volume hierarchy built over a Mesh's triangles, used for picking/raycasting. */
+pub struct Bvh {
+    nodes: Vec<Node>,
+    root: usize,
+}
+
+/** Per-triangle info gathered before the tree is built. */
+struct TriangleInfo {
+    index: usize,
+    centroid: Point3<f32>,
+    bounds: Aabb,
+}
+
+fn axis_component(p: Point3<f32>, axis: usize) -> f32 {
+    match axis {
+        0 => p.x,
+        1 => p.y,
+        _ => p.z,
+    }
+}
+
+/** Recursively builds the subtree over `infos`, pushing nodes into `nodes`, and
+ * returns the index of the node that was built. */
+fn build_node(infos: &mut [TriangleInfo], nodes: &mut Vec<Node>) -> usize {
+    let mut bounds = Aabb::empty();
+    for info in infos.iter() {
+        bounds = bounds.union(&info.bounds);
+    }
+
+    if infos.len() <= LEAF_THRESHOLD {
+        let triangles = infos.iter().map(|info| info.index).collect();
+        nodes.push(Node::Leaf {
+            bounds: bounds,
+            triangles: triangles,
+        });
+        return nodes.len() - 1;
+    }
+
+    // Split along the longest axis at the median centroid.
+    let extent = bounds.max - bounds.min;
+    let axis = if extent.x >= extent.y && extent.x >= extent.z {
+        0
+    } else if extent.y >= extent.z {
+        1
+    } else {
+        2
+    };
+
+    infos.sort_by(|a, b| {
+        axis_component(a.centroid, axis)
+            .partial_cmp(&axis_component(b.centroid, axis))
+            .unwrap()
+    });
+
+    let mid = infos.len() / 2;
+    let (left_infos, right_infos) = infos.split_at_mut(mid);
+
+    // Reserve this node's slot before recursing since its children's indices
+    // aren't known until after they're built.
+    let node_index = nodes.len();
+    nodes.push(Node::Leaf {
+        bounds: Aabb::empty(),
+        triangles: Vec::new(),
+    });
+
+    let left = build_node(left_infos, nodes);
+    let right = build_node(right_infos, nodes);
+
+    nodes[node_index] = Node::Inner {
+        bounds: bounds,
+        left: left,
+        right: right,
+    };
+    node_index
+}
+
+impl Bvh {
+    /** Build a BVH over a mesh's triangles. */
+    pub fn build(mesh: &Mesh) -> Bvh {
+        let num_triangles = mesh.indices.len() / 3;
+
+        let mut infos: Vec<TriangleInfo> = (0..num_triangles)
+            .map(|i| {
+                let p0 = mesh.vertices[mesh.indices[i * 3] as usize].position;
+                let p1 = mesh.vertices[mesh.indices[i * 3 + 1] as usize].position;
+                let p2 = mesh.vertices[mesh.indices[i * 3 + 2] as usize].position;
+
+                let mut bounds = Aabb::empty();
+                bounds.extend(p0);
+                bounds.extend(p1);
+                bounds.extend(p2);
+
+                let centroid = Point3::new((p0.x + p1.x + p2.x) / 3.0,
+                                          (p0.y + p1.y + p2.y) / 3.0,
+                                          (p0.z + p1.z + p2.z) / 3.0);
+
+                TriangleInfo {
+                    index: i,
+                    centroid: centroid,
+                    bounds: bounds,
+                }
+            })
+            .collect();
+
+        let mut nodes = Vec::new();
+        let root = if infos.is_empty() {
+            nodes.push(Node::Leaf {
+                bounds: Aabb::empty(),
+                triangles: Vec::new(),
+            });
+            0
+        } else {
+            build_node(&mut infos, &mut nodes)
+        };
+
+        Bvh {
+            nodes: nodes,
+            root: root,
+        }
+    }
+
+    /** Traverse the BVH, returning the nearest ray-triangle intersection, if any. */
+    pub fn intersect(&self, mesh: &Mesh, origin: Point3<f32>, dir: Vector3<f32>) -> Option<Hit> {
+        let inv_dir = Vector3::new(1.0 / dir.x, 1.0 / dir.y, 1.0 / dir.z);
+
+        let mut best: Option<Hit> = None;
+        let mut stack = vec![self.root];
+
+        while let Some(node_index) = stack.pop() {
+            let t_max = best.as_ref().map(|hit| hit.t).unwrap_or(f32::INFINITY);
+
+            match self.nodes[node_index] {
+                Node::Inner { ref bounds, left, right } => {
+                    if bounds.intersect(origin, inv_dir, t_max).is_some() {
+                        stack.push(left);
+                        stack.push(right);
+                    }
+                }
+                Node::Leaf { ref bounds, ref triangles } => {
+                    if bounds.intersect(origin, inv_dir, t_max).is_none() {
+                        continue;
+                    }
+
+                    for &triangle_index in triangles {
+                        let p0 = mesh.vertices[mesh.indices[triangle_index * 3] as usize].position;
+                        let p1 = mesh.vertices[mesh.indices[triangle_index * 3 + 1] as usize]
+                            .position;
+                        let p2 = mesh.vertices[mesh.indices[triangle_index * 3 + 2] as usize]
+                            .position;
+
+                        if let Some((t, u, v)) = moller_trumbore(origin, dir, p0, p1, p2) {
+                            let is_closer = best.as_ref().map(|hit| t < hit.t).unwrap_or(true);
+                            if is_closer {
+                                best = Some(Hit {
+                                    t: t,
+                                    triangle_index: triangle_index,
+                                    bary: (u, v),
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        best
+    }
+}
+
+/**
+ * Möller–Trumbore ray-triangle intersection, returning `(t, u, v)` on a hit.
+ *
+ * Rejects intersections behind the ray origin or outside the triangle, i.e.
+ * `u < 0`, `v < 0`, or `u + v > 1`.
+ */
+fn moller_trumbore(origin: Point3<f32>,
+                   dir: Vector3<f32>,
+                   p0: Point3<f32>,
+                   p1: Point3<f32>,
+                   p2: Point3<f32>)
+                   -> Option<(f32, f32, f32)> {
+    const EPSILON: f32 = 1e-6;
+
+    let e1 = p1 - p0;
+    let e2 = p2 - p0;
+
+    let pvec = dir.cross(e2);
+    let det = e1.dot(pvec);
+    if det.abs() < EPSILON {
+        return None; // Ray is parallel to the triangle.
+    }
+    let inv_det = 1.0 / det;
+
+    let tvec = origin - p0;
+    let u = tvec.dot(pvec) * inv_det;
+    if u < 0.0 || u > 1.0 {
+        return None;
+    }
+
+    let qvec = tvec.cross(e1);
+    let v = dir.dot(qvec) * inv_det;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+
+    let t = e2.dot(qvec) * inv_det;
+    if t < EPSILON {
+        return None; // Intersection is behind the ray origin.
+    }
+
+    Some((t, u, v))
+}
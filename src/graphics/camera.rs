@@ -1,14 +1,94 @@
 extern crate cgmath;
 
-use cgmath::{Quaternion, Vector3};
+use cgmath::{Deg, InnerSpace, Matrix4, Point3, Vector3, perspective};
+
+/** Default near/far clip planes used by `projection_matrix`. */
+const DEFAULT_NEAR: f32 = 0.1;
+const DEFAULT_FAR: f32 = 1000.0;
+
+/** Clamp applied to pitch, in degrees, so looking up/down can't flip the camera over. */
+const MAX_PITCH: f32 = 89.0;
+
+/** A direction the camera can be translated along in `process_movement`. */
+pub enum MoveDirection {
+    Forward,
+    Backward,
+    Left,
+    Right,
+    Up,
+    Down,
+}
 
 pub struct Camera {
-    position: Vector3<f32>,
-    rotation: Quaternion,
+    pub position: Vector3<f32>,
+
+    pub front: Vector3<f32>,
+    pub up: Vector3<f32>,
+
+    pub fov: f32,
+    pub zoom: f32,
+}
+
+impl Camera {
+    /** Create a camera at `position` looking down -Z with +Y up. */
+    pub fn new(position: Vector3<f32>) -> Camera {
+        Camera {
+            position: position,
+            front: Vector3::new(0.0, 0.0, -1.0),
+            up: Vector3::new(0.0, 1.0, 0.0),
+            fov: 45.0,
+            zoom: 45.0,
+        }
+    }
+
+    /** The view matrix for this camera's position and front/up basis. */
+    pub fn view_matrix(&self) -> Matrix4<f32> {
+        let eye = Point3::new(self.position.x, self.position.y, self.position.z);
+        let center = eye + self.front;
+        Matrix4::look_at(eye, center, self.up)
+    }
+
+    /** The perspective projection matrix for a viewport of the given aspect ratio,
+     * using `zoom` as the vertical field of view. */
+    pub fn projection_matrix(&self, aspect: f32) -> Matrix4<f32> {
+        perspective(Deg(self.zoom), aspect, DEFAULT_NEAR, DEFAULT_FAR)
+    }
+
+    /** Translate the camera along its front/right/up axes. */
+    pub fn process_movement(&mut self, direction: MoveDirection, delta: f32) {
+        let right = self.front.cross(self.up).normalize();
+        match direction {
+            MoveDirection::Forward => self.position += self.front * delta,
+            MoveDirection::Backward => self.position -= self.front * delta,
+            MoveDirection::Left => self.position -= right * delta,
+            MoveDirection::Right => self.position += right * delta,
+            MoveDirection::Up => self.position += self.up * delta,
+            MoveDirection::Down => self.position -= self.up * delta,
+        }
+    }
+
+    /** Apply an FPS-style mouse-look delta, clamping pitch to avoid flipping over. */
+    pub fn process_look(&mut self, yaw_delta: f32, pitch_delta: f32) {
+        let (yaw, pitch) = euler_from_front(self.front);
+
+        let yaw = yaw + yaw_delta;
+        let pitch = (pitch + pitch_delta).max(-MAX_PITCH).min(MAX_PITCH);
+
+        self.front = front_from_euler(yaw, pitch);
+    }
+}
+
+/** Recover yaw/pitch, in degrees, from a normalized front vector. */
+fn euler_from_front(front: Vector3<f32>) -> (f32, f32) {
+    let pitch = front.y.asin().to_degrees();
+    let yaw = front.z.atan2(front.x).to_degrees();
+    (yaw, pitch)
+}
 
-    front: Vector<f32>,
-    up: Vector3<f32>,
+/** Build a normalized front vector from yaw/pitch, in degrees. */
+fn front_from_euler(yaw: f32, pitch: f32) -> Vector3<f32> {
+    let yaw = yaw.to_radians();
+    let pitch = pitch.to_radians();
 
-    fov: f32,
-    zoom: f32,
+    Vector3::new(yaw.cos() * pitch.cos(), pitch.sin(), yaw.sin() * pitch.cos()).normalize()
 }
@@ -0,0 +1,43 @@
+extern crate cgmath;
+
+use cgmath::Vector3;
+use graphics::texture::Texture;
+
+/**
+ * Material parameters parsed from a .mtl file, along with any texture maps
+ * it references.
+ */
+pub struct Material {
+    pub ambient: Vector3<f32>,
+    pub diffuse: Vector3<f32>,
+    pub specular: Vector3<f32>,
+    pub shininess: f32,
+
+    pub optical_density: Option<f32>,
+    pub dissolve: Option<f32>,
+    pub illum: Option<u32>,
+
+    pub ambient_texture: Option<Texture>,
+    pub diffuse_texture: Option<Texture>,
+    pub specular_texture: Option<Texture>,
+    pub normal_texture: Option<Texture>,
+}
+
+impl Material {
+    /** Create a Material with all color params zeroed and no textures. */
+    pub fn new() -> Material {
+        Material {
+            ambient: Vector3::new(0.0, 0.0, 0.0),
+            diffuse: Vector3::new(0.0, 0.0, 0.0),
+            specular: Vector3::new(0.0, 0.0, 0.0),
+            shininess: 0.0,
+            optical_density: None,
+            dissolve: None,
+            illum: None,
+            ambient_texture: None,
+            diffuse_texture: None,
+            specular_texture: None,
+            normal_texture: None,
+        }
+    }
+}
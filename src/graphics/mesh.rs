@@ -2,8 +2,10 @@ extern crate cgmath;
 extern crate gl;
 
 use cgmath::{Point3, Vector2, Vector3};
+use graphics::bvh::{Bvh, Hit};
 use graphics::material::Material;
 use graphics::shader::Shader;
+use std::cell::RefCell;
 use std::ffi::CStr;
 use std::mem::size_of;
 use std::os::raw::c_void;
@@ -27,6 +29,7 @@ pub struct Vertex {
     pub position: Point3<f32>,
     pub normal: Vector3<f32>,
     pub tex_coords: Vector2<f32>,
+    pub tangent: Vector3<f32>,
 }
 
 /**
@@ -43,6 +46,9 @@ pub struct Mesh {
     vao: u32,
     vbo: u32,
     ebo: u32,
+
+    // Lazily built the first time `intersect` is called.
+    bvh: RefCell<Option<Bvh>>,
 }
 
 impl Mesh {
@@ -92,6 +98,13 @@ impl Mesh {
                                     gl::FALSE,
                                     size,
                                     offset_of!(Vertex, tex_coords) as *const c_void);
+            gl::EnableVertexAttribArray(3);
+            gl::VertexAttribPointer(3,
+                                    3,
+                                    gl::FLOAT,
+                                    gl::FALSE,
+                                    size,
+                                    offset_of!(Vertex, tangent) as *const c_void);
 
             gl::BindVertexArray(0);
         }
@@ -103,22 +116,71 @@ impl Mesh {
             vao: vao,
             vbo: vbo,
             ebo: ebo,
+            bvh: RefCell::new(None),
         }
     }
 
+    /** Cast a ray against the mesh, returning the nearest intersection, if any.
+     *
+     * Builds and caches a BVH over the mesh's triangles on first use.
+     */
+    pub fn intersect(&self, origin: Point3<f32>, dir: Vector3<f32>) -> Option<Hit> {
+        let mut bvh = self.bvh.borrow_mut();
+        if bvh.is_none() {
+            *bvh = Some(Bvh::build(self));
+        }
+        bvh.as_ref().unwrap().intersect(self, origin, dir)
+    }
+
     pub unsafe fn render(&self, shader: &Shader) {
         debug_assert!(self.materials.len() <= 1);
 
-        // Bind the material textures.
+        // Upload the material params and bind each available texture to its own unit.
         for material in self.materials.iter() {
-            match material.diffuse_texture {
-                Some(ref texture) => {
-                    gl::Uniform1i(gl::GetUniformLocation(shader.id,
-                                                         c_str!("diffuse_texture").as_ptr()),
-                                  0);
-                    gl::BindTexture(gl::TEXTURE_2D, texture.id);
-                }
-                None => (),
+            gl::Uniform3f(gl::GetUniformLocation(shader.id, c_str!("material.ambient").as_ptr()),
+                         material.ambient.x,
+                         material.ambient.y,
+                         material.ambient.z);
+            gl::Uniform3f(gl::GetUniformLocation(shader.id, c_str!("material.diffuse").as_ptr()),
+                         material.diffuse.x,
+                         material.diffuse.y,
+                         material.diffuse.z);
+            gl::Uniform3f(gl::GetUniformLocation(shader.id, c_str!("material.specular").as_ptr()),
+                         material.specular.x,
+                         material.specular.y,
+                         material.specular.z);
+            gl::Uniform1f(gl::GetUniformLocation(shader.id, c_str!("material.shininess").as_ptr()),
+                         material.shininess);
+
+            let mut texture_unit: u32 = 0;
+
+            if let Some(ref texture) = material.diffuse_texture {
+                gl::ActiveTexture(gl::TEXTURE0 + texture_unit);
+                gl::Uniform1i(gl::GetUniformLocation(shader.id, c_str!("diffuse_texture").as_ptr()),
+                             texture_unit as i32);
+                gl::BindTexture(gl::TEXTURE_2D, texture.id);
+                texture_unit += 1;
+            }
+            if let Some(ref texture) = material.specular_texture {
+                gl::ActiveTexture(gl::TEXTURE0 + texture_unit);
+                gl::Uniform1i(gl::GetUniformLocation(shader.id, c_str!("specular_texture").as_ptr()),
+                             texture_unit as i32);
+                gl::BindTexture(gl::TEXTURE_2D, texture.id);
+                texture_unit += 1;
+            }
+            if let Some(ref texture) = material.ambient_texture {
+                gl::ActiveTexture(gl::TEXTURE0 + texture_unit);
+                gl::Uniform1i(gl::GetUniformLocation(shader.id, c_str!("ambient_texture").as_ptr()),
+                             texture_unit as i32);
+                gl::BindTexture(gl::TEXTURE_2D, texture.id);
+                texture_unit += 1;
+            }
+            if let Some(ref texture) = material.normal_texture {
+                gl::ActiveTexture(gl::TEXTURE0 + texture_unit);
+                gl::Uniform1i(gl::GetUniformLocation(shader.id, c_str!("normal_texture").as_ptr()),
+                             texture_unit as i32);
+                gl::BindTexture(gl::TEXTURE_2D, texture.id);
+                texture_unit += 1;
             }
         }
 
@@ -0,0 +1,593 @@
+extern crate cgmath;
+extern crate image;
+
+use cgmath::{InnerSpace, Point3, Vector2, Vector3, Zero};
+use graphics::material::Material;
+use graphics::mesh::{Mesh, Vertex};
+use graphics::texture::{Texture, TextureBuilder};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+static MATERIAL_PATH: &'static str = "data/materials";
+static TEXTURE_PATH: &'static str = "data/textures";
+
+/** Errors which can occur while loading a .obj/.mtl asset. */
+#[derive(Debug)]
+pub enum LoadError {
+    /** Failed to open or read a file. */
+    Io(io::Error),
+    /** A `usemtl` referenced a material which was never declared via `mtllib`. */
+    MissingMaterial(String),
+    /** A token which requires an argument, e.g. `usemtl`/`mtllib`/a texture map, had none. */
+    MissingArgument { line: usize, token: String },
+    /** An unrecognized token was encountered at the start of a line, or a
+     * `v`/`vn`/`vt`/`K*` line had too few components. */
+    InvalidToken { line: usize, token: String },
+    /** A numeric token couldn't be parsed as a float. */
+    ParseFloat { line: usize },
+    /** A face had fewer than three vertices, or a face vertex was missing its
+     * required position index. */
+    MissingIndex,
+    /** A face index was zero (.obj indices are 1-based) or out of range for what
+     * had been parsed so far. */
+    InvalidIndex,
+    /** A referenced texture image failed to decode. */
+    Image(String),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LoadError::Io(ref e) => write!(f, "I/O error: {}", e),
+            LoadError::MissingMaterial(ref name) => {
+                write!(f, "referenced material {} which was never loaded", name)
+            }
+            LoadError::MissingArgument { line, ref token } => {
+                write!(f, "{} on line {} is missing its argument", token, line)
+            }
+            LoadError::InvalidToken { line, ref token } => {
+                write!(f, "invalid token {} on line {}", token, line)
+            }
+            LoadError::ParseFloat { line } => write!(f, "failed to parse float on line {}", line),
+            LoadError::MissingIndex => {
+                write!(f, "a face must have at least three vertices, each with a position index")
+            }
+            LoadError::InvalidIndex => {
+                write!(f, "a face index was zero or out of range for the elements parsed so far")
+            }
+            LoadError::Image(ref reason) => write!(f, "failed to load texture: {}", reason),
+        }
+    }
+}
+
+impl Error for LoadError {
+    fn description(&self) -> &str {
+        match *self {
+            LoadError::Io(ref e) => e.description(),
+            LoadError::MissingMaterial(_) => "referenced an undeclared material",
+            LoadError::MissingArgument { .. } => "token is missing its argument",
+            LoadError::InvalidToken { .. } => "invalid token",
+            LoadError::ParseFloat { .. } => "failed to parse float",
+            LoadError::MissingIndex => "face has too few vertices or a missing position index",
+            LoadError::InvalidIndex => "face index is zero or out of range",
+            LoadError::Image(_) => "failed to load texture",
+        }
+    }
+
+    fn cause(&self) -> Option<&Error> {
+        match *self {
+            LoadError::Io(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for LoadError {
+    fn from(e: io::Error) -> LoadError {
+        LoadError::Io(e)
+    }
+}
+
+/**
+ * Load a Mesh from a wavefront .obj file.
+ *
+ * Faces may have any number of vertices; n-gons are triangulated as a fan.
+ */
+pub fn load(obj_path: &str) -> Result<Mesh, LoadError> {
+    let mut positions: Vec<Point3<f32>> = Vec::new();
+    let mut normals: Vec<Vector3<f32>> = Vec::new();
+    let mut tex_coords: Vec<Vector2<f32>> = Vec::new();
+    let mut faces: Vec<Face> = Vec::new();
+    let mut materials: Vec<Material> = Vec::new();
+    let mut material_indices_by_name: HashMap<String, u32> = HashMap::new();
+
+    let file = File::open(obj_path)?;
+
+    let reader = BufReader::new(file);
+
+    let mut active_mat_index = None;
+
+    for (line_index, line) in reader.lines().enumerate() {
+        let line_number = line_index + 1;
+        let line = line?;
+        let mut words = line[..].split_whitespace();
+
+        match words.next() {
+            Some("#") | None => (), // Comment or nothing.
+            Some("v") => {
+                // Vertex.
+                let (v1, v2, v3) = (words.next(), words.next(), words.next());
+                positions.push(parse_vertex_position(v1, v2, v3, line_number)?);
+            }
+            Some("vt") => {
+                // Vertex texture.
+                let (tx1, tx2) = (words.next(), words.next());
+                tex_coords.push(parse_tex_coords(tx1, tx2, line_number)?);
+            }
+            Some("vn") => {
+                // Vertex normal.
+                let (n1, n2, n3) = (words.next(), words.next(), words.next());
+                normals.push(parse_vertex_normal(n1, n2, n3, line_number)?);
+
+            }
+            Some("f") => {
+                // Face.
+                let tokens: Vec<&str> = words.collect();
+                faces.push(parse_face(&tokens,
+                                      active_mat_index,
+                                      positions.len(),
+                                      tex_coords.len(),
+                                      normals.len())?);
+            }
+            Some("g") => (), // Group. TODO(orglofch): We assume there's 1 right now.
+            Some("s") => (), // Smooth shading.
+            Some("o") => (), // Object.
+            Some("usemtl") => {
+                // Object material.
+                let name = words.next()
+                    .ok_or(LoadError::MissingArgument {
+                        line: line_number,
+                        token: "usemtl".to_owned(),
+                    })?
+                    .to_owned();
+                active_mat_index = match material_indices_by_name.get(&name) {
+                    Some(&i) => Some(i),
+                    None => return Err(LoadError::MissingMaterial(name)),
+                };
+            }
+            Some("mtllib") => {
+                // Material library.
+                let file = words.next();
+                let name = match words.next() {
+                    Some(name) => name.to_owned(),
+                    None => format!("material{}", materials.len()),
+                };
+                let file = file.ok_or(LoadError::MissingArgument {
+                    line: line_number,
+                    token: "mtllib".to_owned(),
+                })?;
+                material_indices_by_name.insert(name, materials.len() as u32);
+                materials.push(read_material(file)?);
+            }
+            Some(token) => {
+                return Err(LoadError::InvalidToken {
+                    line: line_number,
+                    token: token.to_owned(),
+                })
+            }
+        }
+    }
+    Ok(reindex_faces(positions, normals, tex_coords, faces, materials))
+}
+
+
+// TODO(orglofch): Maybe make custom hash.
+// Indices are resolved to 0-based offsets at parse time, so callers never need to
+// special-case the OBJ spec's negative/relative indices again.
+#[derive(Eq, Hash, PartialEq)]
+struct FaceIndex {
+    p_index: usize,
+    tx_index: Option<usize>,
+    n_index: Option<usize>,
+}
+
+struct Face {
+    indices: Vec<FaceIndex>,
+    mat_index: Option<u32>,
+}
+
+impl Face {
+    /** Generate a face normal, given a set of positions. */
+    pub fn normal(&self, positions: &Vec<Point3<f32>>) -> Vector3<f32> {
+        let p0 = positions[self.indices[0].p_index];
+        let e1 = positions[self.indices[1].p_index] - p0;
+        let e2 = positions[self.indices[2].p_index] - p0;
+
+        e1.cross(e2).normalize()
+    }
+}
+
+/**
+ * Resolves a raw 1-based (or negative/relative) .obj index against the number of
+ * elements parsed so far, returning a 0-based index.
+ *
+ * A positive `raw` maps to `raw - 1`. A negative `raw` counts backwards from the
+ * most recently declared element, i.e. it maps to `len + raw`. `raw == 0` is
+ * invalid (.obj indices are 1-based), and any result outside `[0, len)` is
+ * rejected rather than silently wrapping/overflowing.
+ */
+fn resolve_index(raw: i64, len: usize) -> Result<usize, LoadError> {
+    if raw == 0 {
+        return Err(LoadError::InvalidIndex);
+    }
+
+    let resolved = if raw > 0 { raw - 1 } else { len as i64 + raw };
+
+    if resolved < 0 || resolved as usize >= len {
+        return Err(LoadError::InvalidIndex);
+    }
+    Ok(resolved as usize)
+}
+
+/** Parses a .obj vertex line into x, y, z position. */
+fn parse_vertex_position(v1: Option<&str>,
+                         v2: Option<&str>,
+                         v3: Option<&str>,
+                         line: usize)
+                         -> Result<Point3<f32>, LoadError> {
+    let (x, y, z) = match (v1, v2, v3) {
+        (Some(v1), Some(v2), Some(v3)) => {
+            match (v1.parse::<f32>(), v2.parse::<f32>(), v3.parse::<f32>()) {
+                (Ok(x), Ok(y), Ok(z)) => (x, y, z),
+                _ => return Err(LoadError::ParseFloat { line: line }),
+            }
+        }
+        _ => {
+            return Err(LoadError::InvalidToken {
+                line: line,
+                token: "v".to_owned(),
+            })
+        }
+    };
+    Ok(Point3::new(x, y, z))
+}
+
+/** Parses a .obj normal line into x, y, z normals. */
+fn parse_vertex_normal(n1: Option<&str>,
+                       n2: Option<&str>,
+                       n3: Option<&str>,
+                       line: usize)
+                       -> Result<Vector3<f32>, LoadError> {
+    let (x, y, z) = match (n1, n2, n3) {
+        (Some(n1), Some(n2), Some(n3)) => {
+            match (n1.parse::<f32>(), n2.parse::<f32>(), n3.parse::<f32>()) {
+                (Ok(x), Ok(y), Ok(z)) => (x, y, z),
+                _ => return Err(LoadError::ParseFloat { line: line }),
+            }
+        }
+        _ => {
+            return Err(LoadError::InvalidToken {
+                line: line,
+                token: "vn".to_owned(),
+            })
+        }
+    };
+    Ok(Vector3::new(x, y, z))
+}
+
+/** Parses a .obj tex-coord line into s, t texture coordinates. */
+fn parse_tex_coords(tx1: Option<&str>,
+                    tx2: Option<&str>,
+                    line: usize)
+                    -> Result<Vector2<f32>, LoadError> {
+    let (s, t) = match (tx1, tx2) {
+        (Some(tx1), Some(tx2)) => {
+            match (tx1.parse::<f32>(), tx2.parse::<f32>()) {
+                (Ok(s), Ok(t)) => (s, t),
+                _ => return Err(LoadError::ParseFloat { line: line }),
+            }
+        }
+        _ => {
+            return Err(LoadError::InvalidToken {
+                line: line,
+                token: "vt".to_owned(),
+            })
+        }
+    };
+    Ok(Vector2::new(s, t))
+}
+
+/** Parses a single vertex index for a face, resolving it against the number of
+ * positions/tex-coords/normals parsed so far. */
+fn parse_face_index(vertex: &str,
+                    num_positions: usize,
+                    num_tex_coords: usize,
+                    num_normals: usize)
+                    -> Result<FaceIndex, LoadError> {
+    let mut indices = vertex.split('/');
+
+    let p_index = indices
+        .next()
+        .and_then(|i| i.parse::<i64>().ok())
+        .ok_or(LoadError::MissingIndex)?;
+    let p_index = resolve_index(p_index, num_positions)?;
+    let tx_index = indices.next()
+        // A vertex with a position and normal may have an empty texture coordinate.
+        .and_then(|i| if i.is_empty() { None } else { i.parse::<i64>().ok() })
+        .map(|i| resolve_index(i, num_tex_coords))
+        .transpose()?;
+    let n_index = indices.next()
+        .and_then(|i| i.parse::<i64>().ok())
+        .map(|i| resolve_index(i, num_normals))
+        .transpose()?;
+
+    Ok(FaceIndex {
+        p_index: p_index,
+        tx_index: tx_index,
+        n_index: n_index,
+    })
+}
+
+/** Parses a .obj face line into an object container. */
+fn parse_face(tokens: &[&str],
+              active_mat_index: Option<u32>,
+              num_positions: usize,
+              num_tex_coords: usize,
+              num_normals: usize)
+              -> Result<Face, LoadError> {
+    if tokens.len() < 3 {
+        return Err(LoadError::MissingIndex);
+    }
+
+    let indices = tokens.iter()
+        .map(|token| parse_face_index(token, num_positions, num_tex_coords, num_normals))
+        .collect::<Result<Vec<FaceIndex>, LoadError>>()?;
+
+    Ok(Face {
+        indices: indices,
+        mat_index: active_mat_index,
+    })
+}
+
+/** Parses a .mtl colour triple, e.g. the rgb of a `Ka`/`Kd`/`Ks` line. */
+fn parse_color(c1: Option<&str>,
+               c2: Option<&str>,
+               c3: Option<&str>,
+               line: usize)
+               -> Result<Vector3<f32>, LoadError> {
+    match (c1, c2, c3) {
+        (Some(c1), Some(c2), Some(c3)) => {
+            match (c1.parse::<f32>(), c2.parse::<f32>(), c3.parse::<f32>()) {
+                (Ok(r), Ok(g), Ok(b)) => Ok(Vector3::new(r, g, b)),
+                _ => Err(LoadError::ParseFloat { line: line }),
+            }
+        }
+        _ => {
+            Err(LoadError::InvalidToken {
+                line: line,
+                token: "K*".to_owned(),
+            })
+        }
+    }
+}
+
+/** Parses a single scalar .mtl value, e.g. the exponent of an `Ns` line. */
+fn parse_scalar(s: Option<&str>, line: usize) -> Result<f32, LoadError> {
+    s.and_then(|s| s.parse::<f32>().ok()).ok_or(LoadError::ParseFloat { line: line })
+}
+
+/** Loads a texture map referenced by a .mtl `map_*`/`bump` line. */
+fn load_texture_map(texture_file: Option<&str>,
+                    line: usize,
+                    token: &str)
+                    -> Result<Texture, LoadError> {
+    // TODO(orglofch): Read options and args.
+    let texture_file = texture_file.ok_or(LoadError::MissingArgument {
+        line: line,
+        token: token.to_owned(),
+    })?;
+    let texture_path = Path::new(TEXTURE_PATH).join(texture_file);
+    let img = image::open(&texture_path)
+        .map_err(|e| LoadError::Image(format!("{}: {}", texture_path.display(), e)))?;
+
+    // TODO(orglofch): Make safe?
+    Ok(unsafe { TextureBuilder::from_image(img).build() })
+}
+
+/** Reads a .mtl material file. */
+fn read_material(filename: &str) -> Result<Material, LoadError> {
+    let material_path = Path::new(MATERIAL_PATH).join(filename);
+    let file = File::open(material_path)?;
+
+    let reader = BufReader::new(file);
+
+    let mut material = Material::new();
+
+    for (line_index, line) in reader.lines().enumerate() {
+        let line_number = line_index + 1;
+        let line = line?;
+        let mut words = line[..].split_whitespace();
+
+        match words.next() {
+            Some("#") | None => (), // Comment or empty line.
+            Some("newmtl") => (),
+            Some("Ka") => {
+                // Ambient Colour.
+                material.ambient = parse_color(words.next(), words.next(), words.next(), line_number)?;
+            }
+            Some("Kd") => {
+                // Diffuse Colour.
+                material.diffuse = parse_color(words.next(), words.next(), words.next(), line_number)?;
+            }
+            Some("Ks") => {
+                // Specular Colour.
+                material.specular = parse_color(words.next(), words.next(), words.next(), line_number)?;
+            }
+            Some("Ns") => {
+                // Specular Exponent.
+                material.shininess = parse_scalar(words.next(), line_number)?;
+            }
+            Some("Ni") => {
+                // Optical Density.
+                material.optical_density = Some(parse_scalar(words.next(), line_number)?);
+            }
+            Some("d") => {
+                // Dissolve (opacity).
+                material.dissolve = Some(parse_scalar(words.next(), line_number)?);
+            }
+            Some("illum") => {
+                // Illumination Model.
+                material.illum = Some(parse_scalar(words.next(), line_number)? as u32);
+            }
+            Some("map_Ka") => {
+                // Ambient Texture Map.
+                material.ambient_texture = Some(load_texture_map(words.next(), line_number, "map_Ka")?);
+            }
+            Some("map_Kd") => {
+                // Diffuse Texture Map.
+                material.diffuse_texture = Some(load_texture_map(words.next(), line_number, "map_Kd")?);
+            }
+            Some("map_Ks") => {
+                // Specular Texture Map.
+                material.specular_texture = Some(load_texture_map(words.next(), line_number, "map_Ks")?);
+            }
+            Some("map_Bump") | Some("bump") => {
+                // Normal/Bump Map.
+                material.normal_texture = Some(load_texture_map(words.next(), line_number, "map_Bump")?);
+            }
+            Some("map_Ns") => (), // Specular Exponent Map.
+            Some(token) => {
+                return Err(LoadError::InvalidToken {
+                    line: line_number,
+                    token: token.to_owned(),
+                })
+            }
+        }
+    }
+
+    Ok(material)
+}
+
+/**
+ * Reindex faces into a Mesh with a single index buffer.
+ *
+ * OpenGL can only support a single index buffer so we rearrange the vertex
+ * index buffer into unique vertices with respect to face indexes.
+ */
+fn reindex_faces(positions: Vec<Point3<f32>>,
+                 normals: Vec<Vector3<f32>>,
+                 tex_coords: Vec<Vector2<f32>>,
+                 faces: Vec<Face>,
+                 materials: Vec<Material>)
+                 -> Mesh {
+    // Fill a single index buffer by gathering unique vertices.
+    // and arranging them into the buffers.
+
+    // Maps face vertices into their unique index into the new mesh.
+    // TODO(orglofch): Reserve and shrink.
+    let mut final_index_by_face_index: HashMap<FaceIndex, u32> = HashMap::new();
+
+    let mut final_vertices: Vec<Vertex> = Vec::new();
+    let mut final_indices: Vec<u32> = Vec::new();
+
+    for face in faces {
+        // Generate the face normal, using the first three indices, in case it's necessary.
+        // TODO(orglofch): Make this lazy.
+        let face_normal = face.normal(&positions);
+
+        // Resolve each face vertex into its unique index into the new mesh, inserting
+        // a new vertex the first time a given FaceIndex is encountered.
+        let resolved_indices: Vec<u32> = face.indices
+            .into_iter()
+            .map(|index| {
+                match final_index_by_face_index.get(&index) {
+                    Some(&i) => return i,
+                    None => (),
+                }
+
+                let position = positions[index.p_index];
+
+                // If the texture coordinates are provided then use them, otherwise
+                // use a zeroed tex-coord.
+                let tex_coords = match index.tx_index {
+                    Some(i) => tex_coords[i],
+                    None => Vector2::zero(),
+                };
+
+                // If the normal is provided then use it, otherwise use the face normal.
+                let normal = match index.n_index {
+                    Some(i) => normals[i],
+                    None => face_normal,
+                };
+
+                let new_vertex = Vertex {
+                    position: position,
+                    normal: normal,
+                    tex_coords: tex_coords,
+                    tangent: Vector3::zero(),
+                };
+
+                let new_index = final_vertices.len() as u32;
+                final_index_by_face_index.insert(index, new_index);
+                final_vertices.push(new_vertex);
+                new_index
+            })
+            .collect();
+
+        // Triangulate n-gons as a fan rooted at the first vertex: (v0, vi, vi+1)
+        // for i in 1..n-1, accumulating a tangent onto each vertex of every triangle.
+        for i in 1..resolved_indices.len() - 1 {
+            let i0 = resolved_indices[0];
+            let i1 = resolved_indices[i];
+            let i2 = resolved_indices[i + 1];
+
+            let tangent = triangle_tangent(&final_vertices[i0 as usize],
+                                           &final_vertices[i1 as usize],
+                                           &final_vertices[i2 as usize]);
+            final_vertices[i0 as usize].tangent += tangent;
+            final_vertices[i1 as usize].tangent += tangent;
+            final_vertices[i2 as usize].tangent += tangent;
+
+            final_indices.push(i0);
+            final_indices.push(i1);
+            final_indices.push(i2);
+        }
+    }
+
+    // Normalize the accumulated tangents, orthogonalizing each against its vertex
+    // normal via Gram-Schmidt so the tangent basis stays perpendicular to it.
+    for vertex in final_vertices.iter_mut() {
+        let t = vertex.tangent - vertex.normal * vertex.normal.dot(vertex.tangent);
+        vertex.tangent = if t.magnitude2() > 0.0 {
+            t.normalize()
+        } else {
+            Vector3::zero()
+        };
+    }
+
+    Mesh::new(final_vertices, final_indices, materials)
+}
+
+/**
+ * Computes the tangent of the triangle `(v0, v1, v2)` from its position/UV edges:
+ * given edges `e1 = p1 - p0`, `e2 = p2 - p0` and UV deltas `duv1`, `duv2`,
+ * `f = 1 / (duv1.x*duv2.y - duv2.x*duv1.y)` and `tangent = f * (duv2.y*e1 - duv1.y*e2)`.
+ */
+fn triangle_tangent(v0: &Vertex, v1: &Vertex, v2: &Vertex) -> Vector3<f32> {
+    let e1 = v1.position - v0.position;
+    let e2 = v2.position - v0.position;
+    let duv1 = v1.tex_coords - v0.tex_coords;
+    let duv2 = v2.tex_coords - v0.tex_coords;
+
+    let denom = duv1.x * duv2.y - duv2.x * duv1.y;
+    if denom == 0.0 {
+        return Vector3::zero();
+    }
+    let f = 1.0 / denom;
+
+    (e1 * duv2.y - e2 * duv1.y) * f
+}
@@ -9,40 +9,198 @@ pub struct Texture {
     pub id: u32,
 }
 
-impl Texture {
-    // TODO(orglofch): Decouple this from an image so we can programatically
-    // generate textures.
-    pub unsafe fn new(image: DynamicImage) -> Texture {
+/** The pixel layout of a raw texture upload. */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Red,
+    Rg,
+    Rgb,
+    Rgba,
+}
+
+impl Format {
+    fn to_gl(&self) -> u32 {
+        match *self {
+            Format::Red => gl::RED,
+            Format::Rg => gl::RG,
+            Format::Rgb => gl::RGB,
+            Format::Rgba => gl::RGBA,
+        }
+    }
+
+    fn from_image(image: &DynamicImage) -> Format {
+        match *image {
+            ImageLuma8(_) => Format::Red,
+            ImageLumaA8(_) => Format::Rg,
+            ImageRgb8(_) => Format::Rgb,
+            ImageRgba8(_) => Format::Rgba,
+        }
+    }
+}
+
+/** How a texture samples outside the `[0, 1]` UV range. */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WrapMode {
+    Repeat,
+    MirroredRepeat,
+    ClampToEdge,
+    ClampToBorder,
+}
+
+impl WrapMode {
+    fn to_gl(&self) -> u32 {
+        match *self {
+            WrapMode::Repeat => gl::REPEAT,
+            WrapMode::MirroredRepeat => gl::MIRRORED_REPEAT,
+            WrapMode::ClampToEdge => gl::CLAMP_TO_EDGE,
+            WrapMode::ClampToBorder => gl::CLAMP_TO_BORDER,
+        }
+    }
+}
+
+/** How a texture is sampled when magnified/minified. */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Filter {
+    Nearest,
+    Linear,
+}
+
+impl Filter {
+    /** The mag filter never has a mipmap variant. */
+    fn to_gl_mag(&self) -> u32 {
+        match *self {
+            Filter::Nearest => gl::NEAREST,
+            Filter::Linear => gl::LINEAR,
+        }
+    }
+
+    /** The min filter folds in whether mipmaps were generated. */
+    fn to_gl_min(&self, mipmaps: bool) -> u32 {
+        match (*self, mipmaps) {
+            (Filter::Nearest, false) => gl::NEAREST,
+            (Filter::Linear, false) => gl::LINEAR,
+            (Filter::Nearest, true) => gl::NEAREST_MIPMAP_NEAREST,
+            (Filter::Linear, true) => gl::LINEAR_MIPMAP_LINEAR,
+        }
+    }
+}
+
+enum TextureSource {
+    Raw {
+        width: u32,
+        height: u32,
+        format: Format,
+        data: Vec<u8>,
+    },
+    Image(DynamicImage),
+}
+
+/**
+ * Builds a Texture from either raw pixel bytes or a `DynamicImage`, with
+ * configurable wrap/filter modes and an opt-out of mipmap generation.
+ */
+pub struct TextureBuilder {
+    source: TextureSource,
+    wrap_s: WrapMode,
+    wrap_t: WrapMode,
+    min_filter: Filter,
+    mag_filter: Filter,
+    generate_mipmaps: bool,
+}
+
+impl TextureBuilder {
+    /** Build a texture from raw pixel bytes, without depending on the `image` crate. */
+    pub fn from_bytes(data: &[u8], width: u32, height: u32, format: Format) -> TextureBuilder {
+        TextureBuilder::with_source(TextureSource::Raw {
+            width: width,
+            height: height,
+            format: format,
+            data: data.to_vec(),
+        })
+    }
+
+    /** Build a texture from a decoded image. */
+    pub fn from_image(image: DynamicImage) -> TextureBuilder {
+        TextureBuilder::with_source(TextureSource::Image(image))
+    }
+
+    fn with_source(source: TextureSource) -> TextureBuilder {
+        TextureBuilder {
+            source: source,
+            wrap_s: WrapMode::Repeat,
+            wrap_t: WrapMode::Repeat,
+            min_filter: Filter::Linear,
+            mag_filter: Filter::Linear,
+            generate_mipmaps: true,
+        }
+    }
+
+    /** Set the wrap modes applied to the S and T texture coordinates respectively. */
+    pub fn wrap(mut self, s: WrapMode, t: WrapMode) -> TextureBuilder {
+        self.wrap_s = s;
+        self.wrap_t = t;
+        self
+    }
+
+    /** Set the min/mag filters used when sampling the texture. */
+    pub fn filter(mut self, min: Filter, mag: Filter) -> TextureBuilder {
+        self.min_filter = min;
+        self.mag_filter = mag;
+        self
+    }
+
+    /** Opt out of mipmap generation, e.g. for render targets or pixel-art assets. */
+    pub fn generate_mipmaps(mut self, generate: bool) -> TextureBuilder {
+        self.generate_mipmaps = generate;
+        self
+    }
+
+    pub unsafe fn build(self) -> Texture {
         let mut texture_id = 0;
         gl::GenTextures(1, &mut texture_id);
+        gl::BindTexture(gl::TEXTURE_2D, texture_id);
 
-        let data = image.raw_pixels();
-        let format = match image {
-            ImageLuma8(_) => gl::RED,
-            ImageLumaA8(_) => gl::RG,
-            ImageRgb8(_) => gl::RGB,
-            ImageRgba8(_) => gl::RGBA,
-        };
+        match self.source {
+            TextureSource::Raw { width, height, format, data } => {
+                let gl_format = format.to_gl();
+                gl::TexImage2D(gl::TEXTURE_2D,
+                               0,
+                               gl_format as i32,
+                               width as i32,
+                               height as i32,
+                               0,
+                               gl_format,
+                               gl::UNSIGNED_BYTE,
+                               data.as_ptr() as *const c_void);
+            }
+            TextureSource::Image(image) => {
+                let gl_format = Format::from_image(&image).to_gl();
+                let data = image.raw_pixels();
+                gl::TexImage2D(gl::TEXTURE_2D,
+                               0,
+                               gl_format as i32,
+                               image.width() as i32,
+                               image.height() as i32,
+                               0,
+                               gl_format,
+                               gl::UNSIGNED_BYTE,
+                               &data[0] as *const u8 as *const c_void);
+            }
+        }
 
-        gl::BindTexture(gl::TEXTURE_2D, texture_id);
-        gl::TexImage2D(gl::TEXTURE_2D,
-                       0,
-                       format as i32,
-                       image.width() as i32,
-                       image.height() as i32,
-                       0,
-                       format,
-                       gl::UNSIGNED_BYTE,
-                       &data[0] as *const u8 as *const c_void);
-        gl::GenerateMipmap(gl::TEXTURE_2D);
-
-        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::REPEAT as i32);
-        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::REPEAT as i32);
-        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR_MIPMAP_LINEAR as i32);
-        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
-
-        Texture {
-            id: texture_id
+        if self.generate_mipmaps {
+            gl::GenerateMipmap(gl::TEXTURE_2D);
         }
+
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, self.wrap_s.to_gl() as i32);
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, self.wrap_t.to_gl() as i32);
+        gl::TexParameteri(gl::TEXTURE_2D,
+                          gl::TEXTURE_MIN_FILTER,
+                          self.min_filter.to_gl_min(self.generate_mipmaps) as i32);
+        gl::TexParameteri(gl::TEXTURE_2D,
+                          gl::TEXTURE_MAG_FILTER,
+                          self.mag_filter.to_gl_mag() as i32);
+
+        Texture { id: texture_id }
     }
 }